@@ -0,0 +1,118 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - common.rs
+ *
+ * Shared data types and constants used by reducers and game logic
+ * throughout the module. Kept free of any SpacetimeDB table definitions
+ * so it can be imported from anywhere without creating cycles.
+ */
+
+use spacetimedb::SpacetimeType;
+
+#[derive(SpacetimeType, Clone, Debug, Default, PartialEq)]
+pub struct Vector3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+// Raw client input for a single tick, tagged with a sequence number so the
+// server can detect drops/reordering and reconcile accordingly.
+#[derive(SpacetimeType, Clone, Debug, Default)]
+pub struct InputState {
+    pub sequence: u32,
+    pub forward: bool,
+    pub backward: bool,
+    pub left: bool,
+    pub right: bool,
+    pub sprint: bool,
+    pub jump: bool,
+    pub attack: bool,
+    pub cast_spell: bool,
+}
+
+// Base grid movement speed, in cells/second (reserved for non-grid-snapped movement).
+pub const PLAYER_SPEED: f32 = 5.0;
+// Multiplier applied to movement while sprinting.
+pub const SPRINT_MULTIPLIER: f32 = 1.8;
+
+// Starting/maximum stamina for a player.
+pub const STAMINA_MAX: f32 = 100.0;
+// Stamina regenerated per second on every game tick, up to the player's maximum.
+pub const STAMINA_REGEN_PER_SEC: f32 = 15.0;
+// Stamina spent on a single sprint dash.
+pub const SPRINT_STAMINA_COST: f32 = 20.0;
+// Minimum stamina required to start a sprint dash.
+pub const SPRINT_STAMINA_THRESHOLD: f32 = 20.0;
+
+// Terrain kind for a single grid cell. Blocked rejects movement outright;
+// other variants are exposed so later systems can react to them (e.g. Water
+// forbidding sprint, Slow raising pathfinding cost).
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerrainType {
+    Walkable,
+    Blocked,
+    Water,
+    Slow,
+}
+
+// The cardinal direction a player is facing, quantized from their yaw.
+// Stored on PlayerData so clients and server agree on orientation, and used
+// to turn forward/backward/left/right input into grid offsets.
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Facing {
+    #[default]
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Facing {
+    // Quantizes a yaw in radians (0 = looking down -Z, increasing clockwise,
+    // matching Three.js conventions) to the nearest cardinal direction.
+    pub fn from_yaw(yaw: f32) -> Facing {
+        let two_pi = 2.0 * std::f32::consts::PI;
+        let normalized = ((yaw % two_pi) + two_pi) % two_pi;
+        if normalized < std::f32::consts::PI * 0.25 || normalized > std::f32::consts::PI * 1.75 {
+            Facing::North
+        } else if normalized < std::f32::consts::PI * 0.75 {
+            Facing::East
+        } else if normalized < std::f32::consts::PI * 1.25 {
+            Facing::South
+        } else {
+            Facing::West
+        }
+    }
+
+    // The unit grid offset (dx, dz) for moving one cell in this direction.
+    pub fn to_offset(self) -> (i32, i32) {
+        match self {
+            Facing::North => (0, -1),
+            Facing::East => (1, 0),
+            Facing::South => (0, 1),
+            Facing::West => (-1, 0),
+        }
+    }
+
+    pub fn opposite(self) -> Facing {
+        match self {
+            Facing::North => Facing::South,
+            Facing::East => Facing::West,
+            Facing::South => Facing::North,
+            Facing::West => Facing::East,
+        }
+    }
+
+    pub fn right(self) -> Facing {
+        match self {
+            Facing::North => Facing::East,
+            Facing::East => Facing::South,
+            Facing::South => Facing::West,
+            Facing::West => Facing::North,
+        }
+    }
+
+    pub fn left(self) -> Facing {
+        self.right().opposite()
+    }
+}
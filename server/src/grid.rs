@@ -0,0 +1,137 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - grid.rs
+ *
+ * Terrain lookups for the tile-based movement system. Owns the mapping
+ * from world-space grid cells to the `grid_tile` table and the walkability
+ * rules consulted by `player_logic::calculate_new_position`.
+ *
+ * The actual table lookup is behind the `TerrainQuery` trait rather than
+ * baked directly into those callers, so movement/pathfinding logic can be
+ * unit tested against a fake map instead of needing a live ReducerContext.
+ *
+ * Related files:
+ *    - common.rs: TerrainType enum
+ *    - lib.rs: GridTile table definition and the `init` reducer that seeds it
+ *    - player_logic.rs: calls into `is_walkable` before committing a move
+ */
+
+use spacetimedb::{ReducerContext, Table};
+
+use crate::common::{TerrainType, Vector3};
+use crate::GridTile;
+
+// Map bounds. Cells outside this range are treated as Blocked by the
+// ReducerContext terrain lookup regardless of the grid_tile table.
+pub const GRID_MIN: i32 = -50;
+pub const GRID_MAX: i32 = 50;
+
+pub type Cell = (i32, i32);
+
+// Encodes a (x, z) cell into a single i64 primary key for the grid_tile table.
+pub fn cell_key(cell: Cell) -> i64 {
+    ((cell.0 as i64) << 32) | (cell.1 as u32 as i64)
+}
+
+// Converts a world-space position to the grid cell it falls in.
+pub fn world_to_cell(position: &Vector3, cell_size: f32) -> Cell {
+    (
+        (position.x / cell_size).round() as i32,
+        (position.z / cell_size).round() as i32,
+    )
+}
+
+// Source of terrain data for a cell. Implemented for `ReducerContext` to
+// read the live `grid_tile` table; tests implement it against a plain map.
+pub trait TerrainQuery {
+    fn terrain_at(&self, cell: Cell) -> TerrainType;
+}
+
+impl TerrainQuery for ReducerContext {
+    fn terrain_at(&self, cell: Cell) -> TerrainType {
+        if cell.0 < GRID_MIN || cell.0 > GRID_MAX || cell.1 < GRID_MIN || cell.1 > GRID_MAX {
+            return TerrainType::Blocked;
+        }
+
+        self.db
+            .grid_tile()
+            .cell_key()
+            .find(cell_key(cell))
+            .map(|tile| tile.terrain)
+            .unwrap_or(TerrainType::Walkable)
+    }
+}
+
+pub fn terrain_at(query: &impl TerrainQuery, cell: Cell) -> TerrainType {
+    query.terrain_at(cell)
+}
+
+pub fn is_walkable(query: &impl TerrainQuery, cell: Cell) -> bool {
+    terrain_at(query, cell) != TerrainType::Blocked
+}
+
+// A small default map seeded at module init: mostly open ground with a
+// short wall and a pond, just enough to exercise collision and terrain
+// effects until a real map is authored.
+pub fn default_tiles() -> Vec<GridTile> {
+    let mut tiles = Vec::new();
+
+    for x in -3..=3 {
+        tiles.push(GridTile {
+            cell_key: cell_key((x, 5)),
+            x,
+            z: 5,
+            terrain: TerrainType::Blocked,
+        });
+    }
+
+    for x in -2..=2 {
+        for z in 8..=10 {
+            tiles.push(GridTile {
+                cell_key: cell_key((x, z)),
+                x,
+                z,
+                terrain: TerrainType::Water,
+            });
+        }
+    }
+
+    tiles.push(GridTile {
+        cell_key: cell_key((6, 6)),
+        x: 6,
+        z: 6,
+        terrain: TerrainType::Slow,
+    });
+
+    tiles
+}
+
+// A plain in-memory terrain map for tests, so movement/pathfinding logic
+// can be exercised without a live ReducerContext. Cells default to
+// Walkable unless explicitly overridden.
+#[cfg(test)]
+pub mod test_support {
+    use std::collections::HashMap;
+
+    use super::{Cell, TerrainQuery};
+    use crate::common::TerrainType;
+
+    #[derive(Default)]
+    pub struct FakeGrid(HashMap<Cell, TerrainType>);
+
+    impl FakeGrid {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn set(&mut self, cell: Cell, terrain: TerrainType) -> &mut Self {
+            self.0.insert(cell, terrain);
+            self
+        }
+    }
+
+    impl TerrainQuery for FakeGrid {
+        fn terrain_at(&self, cell: Cell) -> TerrainType {
+            self.0.get(&cell).copied().unwrap_or(TerrainType::Walkable)
+        }
+    }
+}
@@ -0,0 +1,99 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - lib.rs
+ *
+ * Module root: declares the SpacetimeDB tables and reducers, and wires
+ * reducers through to the plain functions in player_logic.rs, grid.rs,
+ * and npc.rs that do the actual work. Keeping the tables/reducers here
+ * and the logic in those modules is what lets them be unit tested
+ * without a ReducerContext.
+ */
+
+mod common;
+mod grid;
+mod npc;
+mod player_logic;
+
+use spacetimedb::{reducer, table, Identity, ReducerContext, Table};
+
+use common::{Facing, InputState, TerrainType, Vector3};
+use player_logic::PlayerSnapshot;
+
+#[table(name = grid_tile, public)]
+#[derive(Clone)]
+pub struct GridTile {
+    #[primary_key]
+    pub cell_key: i64,
+    pub x: i32,
+    pub z: i32,
+    pub terrain: TerrainType,
+}
+
+#[table(name = npc, public)]
+#[derive(Clone)]
+pub struct Npc {
+    #[primary_key]
+    #[auto_inc]
+    pub npc_id: u64,
+    pub current_cell: (i32, i32),
+    pub target_cell: (i32, i32),
+    // Cached A* path to target_cell, popped one cell per tick. Recomputed
+    // by npc::advance whenever it's empty or the next step is blocked.
+    pub path: Vec<(i32, i32)>,
+}
+
+#[table(name = player, public)]
+#[derive(Clone)]
+pub struct PlayerData {
+    #[primary_key]
+    pub identity: Identity,
+    pub position: Vector3,
+    pub rotation: Vector3,
+    // Cardinal direction derived from `rotation.y`, recomputed on every
+    // input so clients and server agree on orientation for grid movement.
+    pub facing: Facing,
+    pub current_animation: String,
+    pub input: InputState,
+    pub last_input_seq: u32,
+    pub is_teleporting: bool,
+    pub is_moving: bool,
+    pub is_running: bool,
+    pub is_attacking: bool,
+    pub is_casting: bool,
+    pub stamina: f32,
+    pub stamina_max: f32,
+    // Recent position/rotation snapshots, used to replay out-of-order input
+    // corrections instead of leaving a laggy client permanently desynced.
+    pub snapshot_history: Vec<PlayerSnapshot>,
+}
+
+#[reducer(init)]
+pub fn init(ctx: &ReducerContext) {
+    for tile in grid::default_tiles() {
+        ctx.db.grid_tile().insert(tile);
+    }
+}
+
+#[reducer]
+pub fn update_input_state(
+    ctx: &ReducerContext,
+    input: InputState,
+    client_rot: Vector3,
+    client_animation: String,
+) -> Result<(), String> {
+    let mut player = ctx
+        .db
+        .player()
+        .identity()
+        .find(&ctx.sender)
+        .ok_or_else(|| "Player not found".to_string())?;
+
+    player_logic::update_input_state(ctx, &mut player, input, client_rot, client_animation);
+
+    ctx.db.player().identity().update(player);
+    Ok(())
+}
+
+#[reducer]
+pub fn game_tick(ctx: &ReducerContext, delta_time: f64) {
+    player_logic::update_players_logic(ctx, delta_time);
+}
@@ -0,0 +1,218 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - npc.rs
+ *
+ * Server-side pathfinding for non-player entities: A* search over the grid
+ * plus per-tick path following. `advance` is called once per NPC from
+ * player_logic::update_players_logic on every game_tick.
+ *
+ * Related files:
+ *    - grid.rs: terrain/walkability lookups the A* search runs over
+ *    - lib.rs: Npc table definition
+ *    - player_logic.rs: drives `advance` for every NPC each tick
+ */
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::common::TerrainType;
+use crate::grid::{self, TerrainQuery};
+use crate::Npc;
+
+// Cost to enter a Slow tile, relative to the baseline cost of 1 for Walkable.
+const SLOW_TILE_COST: u32 = 3;
+
+// Cost to step into `cell`, or None if it's Blocked/out of bounds.
+fn step_cost(query: &impl TerrainQuery, cell: (i32, i32)) -> Option<u32> {
+    match grid::terrain_at(query, cell) {
+        TerrainType::Blocked => None,
+        TerrainType::Slow => Some(SLOW_TILE_COST),
+        TerrainType::Walkable | TerrainType::Water => Some(1),
+    }
+}
+
+fn neighbors(query: &impl TerrainQuery, cell: (i32, i32)) -> Vec<((i32, i32), u32)> {
+    [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .into_iter()
+        .filter_map(|(dx, dz)| {
+            let next = (cell.0 + dx, cell.1 + dz);
+            step_cost(query, next).map(|cost| (next, cost))
+        })
+        .collect()
+}
+
+fn heuristic(a: (i32, i32), b: (i32, i32)) -> u32 {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+fn reconstruct_path(came_from: &HashMap<(i32, i32), (i32, i32)>, mut current: (i32, i32)) -> Vec<(i32, i32)> {
+    let mut path = vec![current];
+    while let Some(&prev) = came_from.get(&current) {
+        path.push(prev);
+        current = prev;
+    }
+    path.reverse();
+    path.remove(0); // drop the start cell; callers only want steps to take
+    path
+}
+
+// A* search from `start` to `goal` over grid cells, using the 4 cardinal
+// neighbors that pass the grid's walkability check. Returns the path
+// excluding `start`, in order from the first step through `goal`, or None
+// if `goal` is unreachable.
+pub fn find_path(query: &impl TerrainQuery, start: (i32, i32), goal: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((heuristic(start, goal), start)));
+
+    let mut came_from: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut g_score: HashMap<(i32, i32), u32> = HashMap::new();
+    g_score.insert(start, 0);
+
+    while let Some(Reverse((_, current))) = open.pop() {
+        if current == goal {
+            return Some(reconstruct_path(&came_from, current));
+        }
+
+        let current_g = g_score[&current];
+        for (next, cost) in neighbors(query, current) {
+            let tentative_g = current_g + cost;
+            if tentative_g < *g_score.get(&next).unwrap_or(&u32::MAX) {
+                came_from.insert(next, current);
+                g_score.insert(next, tentative_g);
+                open.push(Reverse((tentative_g + heuristic(next, goal), next)));
+            }
+        }
+    }
+
+    None
+}
+
+// Advances one NPC by a single cell toward its target, recomputing the
+// cached path with A* if it's empty or the next step is no longer
+// walkable. Idles (clears the path) once the target is reached or turns
+// out to be unreachable.
+pub fn advance(query: &impl TerrainQuery, mut npc: Npc) -> Npc {
+    if npc.current_cell == npc.target_cell {
+        npc.path.clear();
+        return npc;
+    }
+
+    if npc.path.is_empty() {
+        npc.path = find_path(query, npc.current_cell, npc.target_cell).unwrap_or_default();
+    }
+
+    match npc.path.first().copied() {
+        Some(next) if grid::is_walkable(query, next) => {
+            npc.current_cell = next;
+            npc.path.remove(0);
+        }
+        Some(_) => {
+            // Something changed underfoot since the path was planned;
+            // drop it and replan from the current cell next tick.
+            npc.path.clear();
+        }
+        None => {
+            // Unreachable target: stay put; the empty path makes the next
+            // tick run A* again.
+        }
+    }
+
+    npc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::TerrainType;
+    use crate::grid::test_support::FakeGrid;
+
+    #[test]
+    fn find_path_takes_the_straight_line_when_unobstructed() {
+        let grid = FakeGrid::new();
+        let path = find_path(&grid, (0, 0), (0, 3)).expect("goal should be reachable");
+        assert_eq!(path, vec![(0, 1), (0, 2), (0, 3)]);
+    }
+
+    #[test]
+    fn find_path_routes_around_a_wall() {
+        let mut grid = FakeGrid::new();
+        // A wall spanning x=-2..=2 at z=1, with a gap at x=3, forces a detour.
+        for x in -2..=2 {
+            grid.set((x, 1), TerrainType::Blocked);
+        }
+        let path = find_path(&grid, (0, 0), (0, 2)).expect("goal should be reachable via the gap");
+        assert_eq!(*path.last().unwrap(), (0, 2));
+        assert!(!path.contains(&(0, 1)), "path should detour around the wall: {:?}", path);
+    }
+
+    #[test]
+    fn find_path_returns_none_when_goal_is_walled_off() {
+        let mut grid = FakeGrid::new();
+        for dx in -1..=1 {
+            for dz in -1..=1 {
+                if (dx, dz) != (0, 0) {
+                    grid.set((dx, dz), TerrainType::Blocked);
+                }
+            }
+        }
+        assert_eq!(find_path(&grid, (0, 0), (5, 5)), None);
+    }
+
+    #[test]
+    fn find_path_crosses_slow_terrain_when_the_detour_costs_more() {
+        let mut grid = FakeGrid::new();
+        // Block the cells beside the Slow tile at (1, 0): going around then
+        // takes 6 steps, while crossing the Slow tile costs 3 + 1 = 4.
+        for z in -1..=1 {
+            if z != 0 {
+                grid.set((1, z), TerrainType::Blocked);
+            }
+        }
+        grid.set((1, 0), TerrainType::Slow);
+        let path = find_path(&grid, (0, 0), (2, 0)).expect("goal should be reachable through the slow tile");
+        assert_eq!(path, vec![(1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn advance_idles_once_current_cell_reaches_target() {
+        let grid = FakeGrid::new();
+        let npc = Npc {
+            npc_id: 1,
+            current_cell: (2, 2),
+            target_cell: (2, 2),
+            path: vec![(3, 3)],
+        };
+        let advanced = advance(&grid, npc);
+        assert_eq!(advanced.current_cell, (2, 2));
+        assert!(advanced.path.is_empty());
+    }
+
+    #[test]
+    fn advance_pops_one_cell_per_tick_toward_the_target() {
+        let grid = FakeGrid::new();
+        let npc = Npc {
+            npc_id: 1,
+            current_cell: (0, 0),
+            target_cell: (0, 2),
+            path: Vec::new(),
+        };
+        let advanced = advance(&grid, npc);
+        assert_eq!(advanced.current_cell, (0, 1));
+        assert_eq!(advanced.path, vec![(0, 2)]);
+    }
+
+    #[test]
+    fn advance_replans_when_the_cached_path_is_blocked_underfoot() {
+        let mut grid = FakeGrid::new();
+        let npc = Npc {
+            npc_id: 1,
+            current_cell: (0, 0),
+            target_cell: (0, 2),
+            path: vec![(0, 1), (0, 2)],
+        };
+        // Something built a wall on the cached path's next step after it was planned.
+        grid.set((0, 1), TerrainType::Blocked);
+        let advanced = advance(&grid, npc);
+        assert_eq!(advanced.current_cell, (0, 0));
+        assert!(advanced.path.is_empty());
+    }
+}
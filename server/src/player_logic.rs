@@ -8,122 +8,157 @@
  * 
  * 1. Movement Calculation:
  *    - calculate_new_position: Computes player movement based on grid coordinates
- *    - Instant teleportation to adjacent grid cells
- *    - Grid-based positioning system
- * 
+ *    - Instant teleportation to adjacent grid cells, or several in a row while sprinting
+ *    - Grid-based positioning system, rejecting moves into blocked/out-of-bounds cells
+ *    - combined_offset: sums forward/backward/left/right into one vector relative to
+ *      Facing, so diagonal and strafing input produce a true diagonal grid step
+ *
  * 2. State Management:
  *    - update_input_state: Updates player state based on client input
  *    - Handles position, animation, and derived state (is_moving, is_running)
+ *    - Gates sprinting on stamina and terrain via wants_sprint
  *    - Translates raw input to game state
- * 
+ *    - Reconciles out-of-order/dropped input via a per-player snapshot ring buffer
+ *
  * 3. Game Tick:
- *    - update_players_logic: Placeholder for periodic player updates
- *    - Currently empty as players are updated directly through input
- *    - Can be extended for server-side simulation (AI, physics, etc.)
- * 
+ *    - update_players_logic: Drives per-tick server-side simulation
+ *    - Players are still updated directly through input reducers
+ *    - Advances NPC pathfinding (see npc.rs) once per NPC per tick
+ *    - Regenerates stamina over time
+ *
  * Extension points:
- *    - Add terrain logic for different grid tiles
  *    - Implement server-side animation determination (commented example provided)
- *    - Add collision detection in calculate_new_position
- *    - Expand update_players_logic for server-side gameplay mechanics
- * 
+ *    - Expand update_players_logic for further server-side gameplay mechanics
+ *
+ * Movement/reconciliation functions take `&impl grid::TerrainQuery` rather than
+ * `&ReducerContext` directly, so they can be unit tested against a FakeGrid
+ * (see the tests module below) instead of needing a live database.
+ *
  * Related files:
  *    - common.rs: Provides shared data types and constants
+ *    - grid.rs: Terrain/collision lookups consulted by calculate_new_position
+ *    - npc.rs: A* pathfinding driven from update_players_logic
  *    - lib.rs: Calls into this module's functions from reducers
  */
 
-use spacetimedb::ReducerContext;
+use spacetimedb::{ReducerContext, SpacetimeType, Table};
 // Import common structs and constants
-use crate::common::{Vector3, InputState, PLAYER_SPEED, SPRINT_MULTIPLIER};
+use crate::common::{
+    Vector3, InputState, Facing, TerrainType, PLAYER_SPEED, SPRINT_MULTIPLIER,
+    SPRINT_STAMINA_COST, SPRINT_STAMINA_THRESHOLD, STAMINA_REGEN_PER_SEC,
+};
 // Import the PlayerData struct definition (assuming it's in lib.rs or common.rs)
 use crate::PlayerData;
+use crate::grid::{self, TerrainQuery};
+use crate::npc;
+
+// Maximum number of recent snapshots retained per player. Bounds both the
+// memory cost of the ring buffer and the prediction window: corrections
+// older than the oldest retained snapshot can no longer be replayed
+// deterministically and are dropped instead (see `reconcile_input`).
+const SNAPSHOT_BUFFER_SIZE: usize = 16;
+
+// A player's state immediately after applying a given input sequence number,
+// kept so a late-arriving correction for that sequence can be replayed
+// forward deterministically instead of leaving the client desynced.
+#[derive(SpacetimeType, Clone, Debug)]
+pub struct PlayerSnapshot {
+    pub input_seq: u32,
+    pub position: Vector3,
+    pub rotation: Vector3,
+    pub input: InputState,
+}
+
+// Inserts a snapshot, replacing any existing entry for the same sequence,
+// keeps history sorted by sequence, and trims it to SNAPSHOT_BUFFER_SIZE.
+fn push_snapshot(history: &mut Vec<PlayerSnapshot>, snapshot: PlayerSnapshot) {
+    history.retain(|s| s.input_seq != snapshot.input_seq);
+    history.push(snapshot);
+    history.sort_by_key(|s| s.input_seq);
+    while history.len() > SNAPSHOT_BUFFER_SIZE {
+        history.remove(0);
+    }
+}
 
 // Grid cell size for movement
 const GRID_CELL_SIZE: f32 = 1.0;
 
-// Grid-based movement where players teleport one square at a time
-pub fn calculate_new_position(position: &Vector3, rotation: &Vector3, input: &InputState, _delta_time: f32) -> Vector3 {
-    let mut new_position = position.clone();
-    
-    // Determine primary movement direction based on input and rotation
-    // We'll only allow one direction of movement at a time for grid-based movement
-    
-    // Get the dominant input direction based on player's facing
-    // We need to determine which cardinal direction the player is primarily facing
-    let yaw = rotation.y;
-    let normalized_yaw = ((yaw % (2.0 * std::f32::consts::PI)) + 2.0 * std::f32::consts::PI) % (2.0 * std::f32::consts::PI);
-    
-    // Cardinal directions in radians (assuming standard orientation where 0 is +Z, and goes clockwise)
-    // In Three.js: 0 radians = looking down negative Z axis
-    // North = -Z, East = +X, South = +Z, West = -X
-    
+// Sums the grid offsets of every pressed direction relative to `facing`
+// (forward/backward along facing, left/right strafing perpendicular to it)
+// into a single combined step. Pressing forward+right, for example, yields
+// a true diagonal step instead of one direction winning out over the other.
+// Opposing inputs (forward+backward, left+right) cancel out, and since each
+// axis only ever receives one contributing pair the result is naturally
+// bounded to {-1, 0, 1} per axis; the clamp just makes that guarantee explicit.
+fn combined_offset(facing: Facing, input: &InputState) -> (i32, i32) {
+    let mut dx = 0;
+    let mut dz = 0;
+
+    let mut add = |dir: Facing| {
+        let (ox, oz) = dir.to_offset();
+        dx += ox;
+        dz += oz;
+    };
+
     if input.forward {
-        // Move in the direction the player is facing (rounded to nearest cardinal)
-        if normalized_yaw < std::f32::consts::PI * 0.25 || normalized_yaw > std::f32::consts::PI * 1.75 {
-            // Facing primarily North (-Z)
-            new_position.z -= GRID_CELL_SIZE;
-        } else if normalized_yaw < std::f32::consts::PI * 0.75 {
-            // Facing primarily East (+X)
-            new_position.x += GRID_CELL_SIZE;
-        } else if normalized_yaw < std::f32::consts::PI * 1.25 {
-            // Facing primarily South (+Z)
-            new_position.z += GRID_CELL_SIZE;
-        } else {
-            // Facing primarily West (-X)
-            new_position.x -= GRID_CELL_SIZE;
-        }
-    } else if input.backward {
-        // Move opposite to the direction the player is facing
-        if normalized_yaw < std::f32::consts::PI * 0.25 || normalized_yaw > std::f32::consts::PI * 1.75 {
-            // Facing primarily North, move South
-            new_position.z += GRID_CELL_SIZE;
-        } else if normalized_yaw < std::f32::consts::PI * 0.75 {
-            // Facing primarily East, move West
-            new_position.x -= GRID_CELL_SIZE;
-        } else if normalized_yaw < std::f32::consts::PI * 1.25 {
-            // Facing primarily South, move North
-            new_position.z -= GRID_CELL_SIZE;
-        } else {
-            // Facing primarily West, move East
-            new_position.x += GRID_CELL_SIZE;
-        }
-    } else if input.right {
-        // Move 90 degrees clockwise from the direction the player is facing
-        if normalized_yaw < std::f32::consts::PI * 0.25 || normalized_yaw > std::f32::consts::PI * 1.75 {
-            // Facing primarily North, move East
-            new_position.x += GRID_CELL_SIZE;
-        } else if normalized_yaw < std::f32::consts::PI * 0.75 {
-            // Facing primarily East, move South
-            new_position.z += GRID_CELL_SIZE;
-        } else if normalized_yaw < std::f32::consts::PI * 1.25 {
-            // Facing primarily South, move West
-            new_position.x -= GRID_CELL_SIZE;
-        } else {
-            // Facing primarily West, move North
-            new_position.z -= GRID_CELL_SIZE;
+        add(facing);
+    }
+    if input.backward {
+        add(facing.opposite());
+    }
+    if input.right {
+        add(facing.right());
+    }
+    if input.left {
+        add(facing.left());
+    }
+
+    (dx.clamp(-1, 1), dz.clamp(-1, 1))
+}
+
+// Grid-based movement where players teleport one square at a time, or
+// several in a row while `sprinting` (see SPRINT_MULTIPLIER), stopping
+// early at the first Blocked cell so a dash still respects collision.
+pub fn calculate_new_position(query: &impl TerrainQuery, position: &Vector3, facing: Facing, input: &InputState, _delta_time: f32, sprinting: bool) -> Vector3 {
+    let (cell_dx, cell_dz) = combined_offset(facing, input);
+    if cell_dx == 0 && cell_dz == 0 {
+        return position.clone();
+    }
+    let dx = cell_dx as f32 * GRID_CELL_SIZE;
+    let dz = cell_dz as f32 * GRID_CELL_SIZE;
+
+    let steps = if sprinting { (SPRINT_MULTIPLIER.round() as i32).max(1) } else { 1 };
+
+    let mut current = position.clone();
+    for _ in 0..steps {
+        let mut candidate = current.clone();
+        candidate.x += dx;
+        candidate.z += dz;
+        // Snap to grid
+        candidate.x = (candidate.x / GRID_CELL_SIZE).round() * GRID_CELL_SIZE;
+        candidate.z = (candidate.z / GRID_CELL_SIZE).round() * GRID_CELL_SIZE;
+
+        let dest_cell = grid::world_to_cell(&candidate, GRID_CELL_SIZE);
+        if !grid::is_walkable(query, dest_cell) {
+            // Reject this step and stop the dash here; a blocked cell further
+            // along shouldn't be skipped over.
+            break;
         }
-    } else if input.left {
-        // Move 90 degrees counter-clockwise from the direction the player is facing
-        if normalized_yaw < std::f32::consts::PI * 0.25 || normalized_yaw > std::f32::consts::PI * 1.75 {
-            // Facing primarily North, move West
-            new_position.x -= GRID_CELL_SIZE;
-        } else if normalized_yaw < std::f32::consts::PI * 0.75 {
-            // Facing primarily East, move North
-            new_position.z -= GRID_CELL_SIZE;
-        } else if normalized_yaw < std::f32::consts::PI * 1.25 {
-            // Facing primarily South, move East
-            new_position.x += GRID_CELL_SIZE;
-        } else {
-            // Facing primarily West, move South
-            new_position.z += GRID_CELL_SIZE;
+        if cell_dx != 0 && cell_dz != 0 {
+            // Diagonal step: also check the two flanking orthogonal cells so
+            // a player can't cut through the corner where two walls meet,
+            // even though the diagonal destination itself is walkable.
+            let current_cell = grid::world_to_cell(&current, GRID_CELL_SIZE);
+            let flank_x = (current_cell.0 + cell_dx, current_cell.1);
+            let flank_z = (current_cell.0, current_cell.1 + cell_dz);
+            if !grid::is_walkable(query, flank_x) || !grid::is_walkable(query, flank_z) {
+                break;
+            }
         }
+        current = candidate;
     }
-    
-    // Snap to grid
-    new_position.x = (new_position.x / GRID_CELL_SIZE).round() * GRID_CELL_SIZE;
-    new_position.z = (new_position.z / GRID_CELL_SIZE).round() * GRID_CELL_SIZE;
-    
-    return new_position;
+
+    current
 }
 
 // Note: Animation determination is currently handled client-side
@@ -141,37 +176,343 @@ pub fn calculate_new_position(position: &Vector3, rotation: &Vector3, input: &In
 //     }
 // }
 
-// Update player state based on input
-pub fn update_input_state(player: &mut PlayerData, input: InputState, client_rot: Vector3, client_animation: String) {
+// Update player state based on input. Dispatches to a plain forward-apply
+// for new input and to reconciliation for a correction (an input whose
+// sequence is older than or equal to what we've already applied).
+pub fn update_input_state(query: &impl TerrainQuery, player: &mut PlayerData, input: InputState, client_rot: Vector3, client_animation: String) {
+    if input.sequence <= player.last_input_seq {
+        reconcile_input(query, player, input, client_rot, client_animation);
+    } else {
+        apply_input(query, player, input, client_rot, client_animation);
+    }
+}
+
+// Whether `input` should trigger a sprint dash right now: the client asked
+// for it, the player has enough stamina banked, and they're not standing in
+// Water (Water forbids sprinting).
+fn wants_sprint(query: &impl TerrainQuery, player: &PlayerData, input: &InputState) -> bool {
+    if !input.sprint || player.stamina < SPRINT_STAMINA_THRESHOLD {
+        return false;
+    }
+    let current_cell = grid::world_to_cell(&player.position, GRID_CELL_SIZE);
+    grid::terrain_at(query, current_cell) != TerrainType::Water
+}
+
+// Applies a new (in-order) input directly, recording a snapshot so a later
+// correction for this sequence can be replayed from it.
+fn apply_input(query: &impl TerrainQuery, player: &mut PlayerData, input: InputState, client_rot: Vector3, client_animation: String) {
+    let sprinting = wants_sprint(query, player, &input);
+    let facing = Facing::from_yaw(client_rot.y);
+
     // Calculate new grid position based on input
     let new_position = calculate_new_position(
+        query,
         &player.position,
-        &client_rot, // Use client rotation for direction calc
+        facing,
         &input,
-        0.0 // Delta time not needed for grid movement
+        0.0, // Delta time not needed for grid movement
+        sprinting,
     );
 
     // Set is_teleporting flag to true to signal instant movement
-    // This will need to be added to the PlayerData struct in lib.rs
     player.is_teleporting = true;
-    
+
     // Update player state
-    player.position = new_position;
-    player.rotation = client_rot;
+    player.position = new_position.clone();
+    player.rotation = client_rot.clone();
+    player.facing = facing;
     player.current_animation = client_animation;
-    player.input = input.clone(); // Store the input that caused this state
     player.last_input_seq = input.sequence;
-    
+
     // Set is_moving to false since we're teleporting
     player.is_moving = false;
-    player.is_running = false;
-    
+    player.is_running = sprinting;
+    if sprinting {
+        player.stamina = (player.stamina - SPRINT_STAMINA_COST).max(0.0);
+    }
+
     player.is_attacking = input.attack;
     player.is_casting = input.cast_spell;
+
+    push_snapshot(&mut player.snapshot_history, PlayerSnapshot {
+        input_seq: input.sequence,
+        position: new_position,
+        rotation: client_rot,
+        input: input.clone(),
+    });
+    player.input = input; // Store the input that caused this state
 }
 
-// Update players logic (called from game_tick)
-pub fn update_players_logic(_ctx: &ReducerContext, _delta_time: f64) {
-    // In the grid-based teleportation system, all movement is handled through keypresses
-    // This function is a placeholder for future expansion (e.g., AI movement on grid)
+// Rolls back to the last snapshot strictly before `input`'s sequence number and
+// replays every buffered input from there forward (the correction itself
+// plus anything we already applied after it), so a laggy/out-of-order
+// client heals to the same deterministic state instead of staying desynced.
+fn reconcile_input(query: &impl TerrainQuery, player: &mut PlayerData, input: InputState, client_rot: Vector3, client_animation: String) {
+    if player.snapshot_history.is_empty() {
+        // Nothing to replay against yet; treat it as a fresh input.
+        apply_input(query, player, input, client_rot, client_animation);
+        return;
+    }
+
+    let baseline_seq = input.sequence.saturating_sub(1);
+    let baseline = match player.snapshot_history.iter().rev().find(|s| s.input_seq <= baseline_seq).cloned() {
+        Some(s) => s,
+        None => {
+            // Either genuinely outside the prediction window (input.sequence
+            // is older than anything buffered), or it targets the oldest
+            // sequence still buffered - either way there's no snapshot of
+            // the *pre*-application state to replay from (the oldest entry
+            // is already that input's post-state, not a baseline). We no
+            // longer have enough history to replay deterministically, so
+            // drop the correction and keep the current authoritative state
+            // instead.
+            return;
+        }
+    };
+    let (mut position, mut rotation) = (baseline.position.clone(), baseline.rotation.clone());
+    let from_seq = baseline.input_seq;
+
+    // Whether this exact sequence was already applied (and billed for
+    // stamina) before this call. A duplicate/retransmitted packet for a
+    // sequence we've already seen replays to the same position but must
+    // not be charged stamina a second time.
+    let already_billed = player.snapshot_history.iter().any(|s| s.input_seq == input.sequence);
+
+    // Everything after the baseline needs replaying: the correction itself,
+    // plus every later input we already have buffered.
+    let mut replay: Vec<PlayerSnapshot> = player.snapshot_history.iter()
+        .filter(|s| s.input_seq > from_seq && s.input_seq != input.sequence)
+        .cloned()
+        .collect();
+    replay.push(PlayerSnapshot {
+        input_seq: input.sequence,
+        position: Vector3::default(),
+        rotation: client_rot.clone(),
+        input: input.clone(),
+    });
+    replay.sort_by_key(|s| s.input_seq);
+
+    let mut replayed = Vec::with_capacity(replay.len());
+    for step in replay {
+        // Replayed steps use the input's own sprint flag rather than
+        // `wants_sprint`: historical stamina/terrain at each buffered
+        // sequence isn't snapshotted, so this is an accepted approximation.
+        let sprinting = step.input.sprint;
+        let facing = Facing::from_yaw(step.rotation.y);
+        position = calculate_new_position(query, &position, facing, &step.input, 0.0, sprinting);
+        rotation = step.rotation.clone();
+        replayed.push(PlayerSnapshot {
+            input_seq: step.input_seq,
+            position: position.clone(),
+            rotation: rotation.clone(),
+            input: step.input,
+        });
+    }
+
+    // Overwrite the corresponding entries in the ring buffer with the
+    // freshly-replayed states.
+    for snapshot in replayed {
+        push_snapshot(&mut player.snapshot_history, snapshot);
+    }
+
+    let sprinting = wants_sprint(query, player, &input);
+    if sprinting && !already_billed {
+        player.stamina = (player.stamina - SPRINT_STAMINA_COST).max(0.0);
+    }
+
+    player.position = position;
+    player.facing = Facing::from_yaw(rotation.y);
+    player.rotation = rotation;
+    player.current_animation = client_animation;
+    player.is_teleporting = true;
+    player.is_moving = false;
+    player.is_running = sprinting;
+    player.is_attacking = input.attack;
+    player.is_casting = input.cast_spell;
+
+    if input.sequence > player.last_input_seq {
+        player.last_input_seq = input.sequence;
+        player.input = input;
+    }
+}
+
+// Update players logic (called from game_tick). Player movement is still
+// driven entirely by input reducers; this is where non-player simulation
+// and periodic resource regen that needs to run every tick lives instead.
+pub fn update_players_logic(ctx: &ReducerContext, delta_time: f64) {
+    for npc in ctx.db.npc().iter() {
+        let advanced = npc::advance(ctx, npc);
+        ctx.db.npc().npc_id().update(advanced);
+    }
+
+    for mut player in ctx.db.player().iter() {
+        if player.stamina < player.stamina_max {
+            player.stamina = (player.stamina + STAMINA_REGEN_PER_SEC * delta_time as f32).min(player.stamina_max);
+            ctx.db.player().identity().update(player);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::STAMINA_MAX;
+    use crate::grid::test_support::FakeGrid;
+    use spacetimedb::Identity;
+
+    fn forward_input(sequence: u32) -> InputState {
+        InputState { sequence, forward: true, ..Default::default() }
+    }
+
+    fn sprinting_forward_input(sequence: u32) -> InputState {
+        InputState { sequence, forward: true, sprint: true, ..Default::default() }
+    }
+
+    fn new_player() -> PlayerData {
+        PlayerData {
+            identity: Identity::from_byte_array([0u8; 32]),
+            position: Vector3::default(),
+            rotation: Vector3::default(),
+            facing: Facing::North,
+            current_animation: String::new(),
+            input: InputState::default(),
+            last_input_seq: 0,
+            is_teleporting: false,
+            is_moving: false,
+            is_running: false,
+            is_attacking: false,
+            is_casting: false,
+            stamina: STAMINA_MAX,
+            stamina_max: STAMINA_MAX,
+            snapshot_history: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn combined_offset_adds_forward_and_right_into_a_diagonal() {
+        let input = InputState { forward: true, right: true, ..Default::default() };
+        assert_eq!(combined_offset(Facing::North, &input), (1, -1));
+    }
+
+    #[test]
+    fn combined_offset_cancels_opposing_inputs() {
+        let input = InputState { forward: true, backward: true, left: true, right: true, ..Default::default() };
+        assert_eq!(combined_offset(Facing::North, &input), (0, 0));
+    }
+
+    #[test]
+    fn calculate_new_position_rejects_a_diagonal_cutting_through_a_corner() {
+        let mut grid = FakeGrid::new();
+        // Facing North with forward+right held steps diagonally to (1, -1);
+        // wall off both cells flanking that corner.
+        grid.set((1, 0), TerrainType::Blocked);
+        grid.set((0, -1), TerrainType::Blocked);
+        let input = InputState { forward: true, right: true, ..Default::default() };
+        let new_position = calculate_new_position(&grid, &Vector3::default(), Facing::North, &input, 0.0, false);
+        assert_eq!(new_position, Vector3::default());
+    }
+
+    #[test]
+    fn push_snapshot_replaces_same_sequence_and_keeps_history_sorted() {
+        let mut history = Vec::new();
+        push_snapshot(&mut history, PlayerSnapshot { input_seq: 2, position: Vector3::default(), rotation: Vector3::default(), input: InputState::default() });
+        push_snapshot(&mut history, PlayerSnapshot { input_seq: 1, position: Vector3::default(), rotation: Vector3::default(), input: InputState::default() });
+        let mut replacement = Vector3::default();
+        replacement.x = 9.0;
+        push_snapshot(&mut history, PlayerSnapshot { input_seq: 1, position: replacement.clone(), rotation: Vector3::default(), input: InputState::default() });
+
+        assert_eq!(history.iter().map(|s| s.input_seq).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(history[0].position, replacement);
+    }
+
+    #[test]
+    fn push_snapshot_trims_to_buffer_size() {
+        let mut history = Vec::new();
+        for seq in 0..(SNAPSHOT_BUFFER_SIZE as u32 + 5) {
+            push_snapshot(&mut history, PlayerSnapshot { input_seq: seq, position: Vector3::default(), rotation: Vector3::default(), input: InputState::default() });
+        }
+        assert_eq!(history.len(), SNAPSHOT_BUFFER_SIZE);
+        assert_eq!(history.first().unwrap().input_seq, 5);
+        assert_eq!(history.last().unwrap().input_seq, SNAPSHOT_BUFFER_SIZE as u32 + 4);
+    }
+
+    #[test]
+    fn reconcile_input_replays_a_correction_and_everything_after_it() {
+        let grid = FakeGrid::new();
+        let mut player = new_player();
+
+        for seq in 1..=3 {
+            update_input_state(&grid, &mut player, forward_input(seq), Vector3::default(), String::new());
+        }
+        assert_eq!(player.position.z, -3.0);
+
+        // Input 2 arrives again (e.g. a duplicated/delayed packet) unchanged;
+        // replaying it and everything buffered after it should reproduce the
+        // exact same authoritative position, not move further.
+        update_input_state(&grid, &mut player, forward_input(2), Vector3::default(), String::new());
+
+        assert_eq!(player.position.z, -3.0);
+        assert_eq!(player.last_input_seq, 3);
+    }
+
+    #[test]
+    fn reconcile_input_drops_a_correction_for_the_oldest_buffered_sequence() {
+        // Regression test: correcting the oldest sequence still in the ring
+        // buffer must not be replayed against its own post-state, which
+        // would double-move the player (and double-charge stamina for a
+        // sprint) instead of leaving the authoritative state untouched.
+        let grid = FakeGrid::new();
+        let mut player = new_player();
+
+        let total_inputs = SNAPSHOT_BUFFER_SIZE as u32 + 4;
+        for seq in 1..=total_inputs {
+            update_input_state(&grid, &mut player, forward_input(seq), Vector3::default(), String::new());
+        }
+        let position_before = player.position.clone();
+        let last_input_seq_before = player.last_input_seq;
+        let oldest_seq = player.snapshot_history.first().unwrap().input_seq;
+
+        update_input_state(&grid, &mut player, forward_input(oldest_seq), Vector3::default(), String::new());
+
+        assert_eq!(player.position, position_before);
+        assert_eq!(player.last_input_seq, last_input_seq_before);
+    }
+
+    #[test]
+    fn reconcile_input_drops_a_correction_older_than_the_buffer() {
+        let grid = FakeGrid::new();
+        let mut player = new_player();
+
+        for seq in 1..=(SNAPSHOT_BUFFER_SIZE as u32 + 4) {
+            update_input_state(&grid, &mut player, forward_input(seq), Vector3::default(), String::new());
+        }
+        let position_before = player.position.clone();
+        let last_input_seq_before = player.last_input_seq;
+
+        update_input_state(&grid, &mut player, forward_input(1), Vector3::default(), String::new());
+
+        assert_eq!(player.position, position_before);
+        assert_eq!(player.last_input_seq, last_input_seq_before);
+    }
+
+    #[test]
+    fn reconcile_input_does_not_rededuct_stamina_for_a_duplicate_sprint_input() {
+        // Regression test: redelivering an already-applied sprint input
+        // (an ordinary retransmit/duplicate) must replay to the same
+        // position without charging stamina a second time.
+        let grid = FakeGrid::new();
+        let mut player = new_player();
+
+        update_input_state(&grid, &mut player, sprinting_forward_input(1), Vector3::default(), String::new());
+        update_input_state(&grid, &mut player, sprinting_forward_input(2), Vector3::default(), String::new());
+        let position_after_seq_2 = player.position.clone();
+        let stamina_after_seq_2 = player.stamina;
+
+        // Sequence 2 arrives again unchanged; it's a correction for a
+        // sequence already in the buffer, not new information.
+        update_input_state(&grid, &mut player, sprinting_forward_input(2), Vector3::default(), String::new());
+
+        assert_eq!(player.position, position_after_seq_2);
+        assert_eq!(player.stamina, stamina_after_seq_2);
+    }
 }